@@ -2,12 +2,188 @@ use serde::de::{Deserializer, Visitor};
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::OnceLock;
+
+/// The lazily-parsed components of a `Url`, cached after the first access.
+#[derive(Clone, Debug, Default)]
+struct UrlParts {
+    scheme: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl UrlParts {
+    fn parse(s: &str) -> UrlParts {
+        // `http::Uri` doesn't know about fragments, so split one off first.
+        let (without_fragment, fragment) = match s.split_once('#') {
+            Some((rest, frag)) => (rest, Some(frag.to_owned())),
+            None => (s, None),
+        };
+
+        // `http::Uri` also only understands ASCII, so punycode the host first.
+        let ascii = authority_to_ascii(without_fragment);
+
+        let Ok(uri) = ascii.parse::<http::Uri>() else {
+            return UrlParts::default();
+        };
+
+        let authority = uri.authority();
+        UrlParts {
+            scheme: uri.scheme_str().map(str::to_owned),
+            host: authority.map(|a| a.host().to_owned()),
+            port: authority.and_then(|a| a.port_u16()),
+            path: uri.path().to_owned(),
+            query: uri.query().map(str::to_owned),
+            fragment,
+        }
+    }
+}
+
+/// Characters that are only ever used by IPv4/IPv6 literal hosts, never by
+/// a domain name. Lets us skip running IDNA over `127.0.0.1` or `[::1]`.
+fn looks_like_ip_host(host: &str) -> bool {
+    host.starts_with('[') || host.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// Convert a host to its ASCII-compatible (punycode) form via IDNA ToASCII,
+/// leaving IPv4/IPv6 literal hosts untouched.
+///
+/// Returns `None` if `host` is not a valid IDNA domain.
+fn host_to_ascii(host: &str) -> Option<String> {
+    if looks_like_ip_host(host) {
+        return Some(host.to_owned());
+    }
+    idna::domain_to_ascii(host).ok()
+}
+
+/// Recover the unicode display form of a (possibly punycode) host, for UIs.
+///
+/// Leaves IPv4/IPv6 literal hosts untouched.
+fn host_to_unicode(host: &str) -> String {
+    if looks_like_ip_host(host) {
+        return host.to_owned();
+    }
+    let (unicode, result) = idna::domain_to_unicode(host);
+    if result.is_ok() {
+        unicode
+    } else {
+        host.to_owned()
+    }
+}
+
+/// Split a `scheme://authority/rest` string into its scheme (including the
+/// `://`), its authority, and everything from the following `/`, `?` or `#`
+/// onward. Returns `None` if
+/// there's no `://` to anchor on (e.g. the `nostr:` scheme has no authority).
+fn split_authority(s: &str) -> Option<(&str, &str, &str)> {
+    let after_scheme = s.find("://")? + 3;
+    let scheme = &s[..after_scheme];
+    let tail = &s[after_scheme..];
+    let end = tail.find(['/', '?', '#']).unwrap_or(tail.len());
+    Some((scheme, &tail[..end], &tail[end..]))
+}
+
+/// Split an authority (`user:pass@host:port`) into its userinfo (with
+/// trailing `@`, if any), host, and port (with leading `:`, if any).
+fn split_authority_parts(authority: &str) -> (&str, &str, &str) {
+    let (userinfo, host_port) = match authority.rfind('@') {
+        Some(i) => (&authority[..=i], &authority[i + 1..]),
+        None => ("", authority),
+    };
+    if let Some(rest) = host_port.strip_prefix('[') {
+        // IPv6 literal: keep the brackets with the host, port starts after `]`.
+        let close = rest.find(']').map_or(host_port.len(), |i| i + 2);
+        (userinfo, &host_port[..close], &host_port[close..])
+    } else {
+        match host_port.find(':') {
+            Some(i) => (userinfo, &host_port[..i], &host_port[i..]),
+            None => (userinfo, host_port, ""),
+        }
+    }
+}
+
+/// Rewrite a URL string so its authority's host is in ASCII-compatible
+/// (punycode) form, so it can then be parsed with `http::Uri`, which only
+/// understands ASCII. A no-op for strings with no `scheme://` authority, or
+/// whose host isn't a valid IDNA domain.
+fn authority_to_ascii(s: &str) -> String {
+    let Some((scheme, authority, rest)) = split_authority(s) else {
+        return s.to_owned();
+    };
+    let (userinfo, host, port) = split_authority_parts(authority);
+    match host_to_ascii(host) {
+        Some(ascii_host) => format!("{scheme}{userinfo}{ascii_host}{port}{rest}"),
+        None => s.to_owned(),
+    }
+}
+
+/// The scheme of a [`Url`], as a typed enum rather than a bare string
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Scheme {
+    /// `ws:`, a plaintext relay connection
+    Ws,
+    /// `wss:`, a TLS relay connection
+    Wss,
+    /// `http:`
+    Http,
+    /// `https:`
+    Https,
+    /// `nostr:`, the NIP-21 URI scheme used for `nostr:npub1…`/`nevent1…`/`naddr1…` links
+    Nostr,
+    /// Any other scheme, stored lowercased
+    Other(String),
+}
+
+impl From<&str> for Scheme {
+    fn from(s: &str) -> Scheme {
+        match s.to_ascii_lowercase().as_str() {
+            "ws" => Scheme::Ws,
+            "wss" => Scheme::Wss,
+            "http" => Scheme::Http,
+            "https" => Scheme::Https,
+            "nostr" => Scheme::Nostr,
+            other => Scheme::Other(other.to_owned()),
+        }
+    }
+}
 
 /// A String representing a Url with a notion of whether it is a valid nostr URL or not
 ///
 /// This Serializes/Deserializes from a string
-#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
-pub struct Url(String, bool);
+#[derive(Clone, Debug)]
+pub struct Url(String, bool, OnceLock<UrlParts>);
+
+// The parsed-component cache is a pure function of the string, so it is
+// deliberately excluded from equality, hashing and ordering.
+impl PartialEq for Url {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1 == other.1
+    }
+}
+
+impl Eq for Url {}
+
+impl std::hash::Hash for Url {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+        self.1.hash(state);
+    }
+}
+
+impl PartialOrd for Url {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Url {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.0, self.1).cmp(&(&other.0, other.1))
+    }
+}
 
 impl std::ops::Deref for Url {
     type Target = str;
@@ -28,14 +204,112 @@ impl Url {
     pub fn new(s: &str) -> Url {
         // Technically, URLs with a trailing slash are different than ones without.
         // But nobody treats them that way, and to do so causes more problems than
-        // it is worth. So we remove the trailing slashes.
-        let s2 = if s.ends_with('/') {
-            &s[0..s.len() - 1]
+        // it is worth. So we remove the trailing slashes. Only the part before the
+        // fragment is considered, so a fragment that itself ends in '/' is left intact.
+        let (body, fragment) = match s.split_once('#') {
+            Some((body, fragment)) => (body, Some(fragment)),
+            None => (s, None),
+        };
+        let body = if body.ends_with('/') {
+            &body[0..body.len() - 1]
         } else {
-            s
+            body
+        };
+        let s2 = match fragment {
+            Some(fragment) => format!("{body}#{fragment}"),
+            None => body.to_owned(),
         };
 
-        Url(s2.to_owned(), s2.parse::<http::Uri>().is_ok())
+        Url(s2.clone(), s2.parse::<http::Uri>().is_ok(), OnceLock::new())
+    }
+
+    /// Wrap an already-canonical string as a `Url` without re-applying
+    /// `new`'s blanket trailing-slash stripping, which would also strip a
+    /// meaningful trailing slash on a deeper path (e.g. `/nostr/`).
+    fn from_canonical(s: String) -> Url {
+        let valid = authority_to_ascii(&s).parse::<http::Uri>().is_ok();
+        Url(s, valid, OnceLock::new())
+    }
+
+    fn parts(&self) -> &UrlParts {
+        self.2.get_or_init(|| UrlParts::parse(&self.0))
+    }
+
+    /// The scheme of the URL, e.g. `wss`
+    pub fn scheme(&self) -> Option<&str> {
+        self.parts().scheme.as_deref()
+    }
+
+    /// The host of the URL, e.g. `relay.example.com`
+    pub fn host(&self) -> Option<&str> {
+        self.parts().host.as_deref()
+    }
+
+    /// The port of the URL, if one was explicitly given
+    pub fn port(&self) -> Option<u16> {
+        self.parts().port
+    }
+
+    /// The path of the URL. A URL with a scheme but no path (e.g.
+    /// `wss://relay.example.com`) reports `/`; empty if the string doesn't
+    /// parse as a URI.
+    pub fn path(&self) -> &str {
+        &self.parts().path
+    }
+
+    /// The query string of the URL, if any, without the leading `?`
+    pub fn query(&self) -> Option<&str> {
+        self.parts().query.as_deref()
+    }
+
+    /// The fragment of the URL, if any, without the leading `#`
+    pub fn fragment(&self) -> Option<&str> {
+        self.parts().fragment.as_deref()
+    }
+
+    /// The scheme of this Url as a typed [`Scheme`]
+    ///
+    /// Unlike [`Url::scheme`], this is read directly off the string rather
+    /// than through `http::Uri`, so it also recognizes schemes with no
+    /// authority, such as `nostr:`.
+    pub fn scheme_kind(&self) -> Option<Scheme> {
+        let colon = self.0.find(':')?;
+        Some(Scheme::from(&self.0[..colon]))
+    }
+
+    /// For a `nostr:` Url (per NIP-21), the bech32 payload after the
+    /// `nostr:` prefix, e.g. `npub1…`, `nevent1…`, `naddr1…`.
+    ///
+    /// Returns `None` for any other scheme.
+    pub fn nostr_bech32_payload(&self) -> Option<&str> {
+        let colon = self.0.find(':')?;
+        if Scheme::from(&self.0[..colon]) != Scheme::Nostr {
+            return None;
+        }
+        Some(&self.0[colon + 1..])
+    }
+
+    /// Create a new Url from a string, normalizing it into canonical form
+    ///
+    /// The string is canonicalized as by [`Url::normalize`], without first
+    /// applying [`Url::new`]'s trailing-slash stripping, so a trailing slash
+    /// on a deeper path (e.g. `/nostr/`) is kept.
+    pub fn new_normalized(s: &str) -> Url {
+        Url::from_canonical(canonicalize(s))
+    }
+
+    /// Compute the canonical form of this Url.
+    ///
+    /// This lowercases the scheme and host, strips the default port for the
+    /// scheme (`80` for `ws`/`http`, `443` for `wss`/`https`), collapses an
+    /// empty path to `""` and drops a bare trailing slash only when there is
+    /// no query or fragment, and upper-cases the hex digits of any
+    /// percent-escapes that can't be decoded outright.
+    ///
+    /// Calling `.normalize()` on an already-normalized string is a no-op, so
+    /// the result is safe to use as a dedup/equality key.
+    pub fn normalize(&self) -> String {
+        canonicalize(&self.0)
     }
 
     /// Get reference to inner string
@@ -45,7 +319,8 @@ impl Url {
 
     /// Check if the URL is a valid relay URL
     pub fn is_valid_relay_url(&self) -> bool {
-        if let Ok(uri) = self.0.parse::<http::Uri>() {
+        let ascii = authority_to_ascii(&self.0);
+        if let Ok(uri) = ascii.parse::<http::Uri>() {
             if let Some(scheme) = uri.scheme() {
                 if scheme.as_str() == "wss" || scheme.as_str() == "ws" {
                     if let Some(authority) = uri.authority() {
@@ -53,10 +328,10 @@ impl Url {
                         if host == host.trim()
                             && !host.starts_with("localhost")
                             && !host.starts_with("127.")
-                            && !host.starts_with("[::1/")
+                            && !host.starts_with("[::1]")
                             && !host.starts_with("[0:")
                         {
-                            return true;
+                            return host_to_ascii(host).is_some();
                         }
                     }
                 }
@@ -65,6 +340,16 @@ impl Url {
         false
     }
 
+    /// Recover the unicode display form of this Url's host, for showing to
+    /// users. Returns the punycode (or plain) host unchanged if it isn't a
+    /// valid IDNA domain, and `None` if the Url has no `scheme://` authority
+    /// (e.g. a `nostr:` URI). An empty authority gives `Some("")`.
+    pub fn unicode_host(&self) -> Option<String> {
+        let (_, authority, _) = split_authority(&self.0)?;
+        let (_, host, _) = split_authority_parts(authority);
+        Some(host_to_unicode(host))
+    }
+
     /// If the Url represents a valid URL
     pub fn is_valid(&self) -> bool {
         self.1
@@ -73,10 +358,271 @@ impl Url {
     // Mock data for testing
     #[allow(dead_code)]
     pub(crate) fn mock() -> Url {
-        Url("wss://example.com".to_string(), true)
+        Url("wss://example.com".to_string(), true, OnceLock::new())
+    }
+}
+
+/// The reason a string could not be parsed as a [`RelayUrl`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    /// The string could not be parsed as a URI at all
+    InvalidUri,
+    /// The scheme was not `ws` or `wss`
+    InvalidScheme,
+    /// There was no host to connect to
+    EmptyHost,
+    /// The host contains whitespace
+    ContainsWhitespace,
+    /// The host is a loopback address, which relays may not use
+    LoopbackNotAllowed,
+    /// The host is an IPv6 address with malformed bracket syntax
+    InvalidIpv6Address,
+    /// The host is not a valid domain name (it failed IDNA processing)
+    InvalidHost,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            ParseError::InvalidUri => "could not be parsed as a URI",
+            ParseError::InvalidScheme => "scheme must be `ws` or `wss`",
+            ParseError::EmptyHost => "missing host",
+            ParseError::ContainsWhitespace => "host contains whitespace",
+            ParseError::LoopbackNotAllowed => "loopback hosts are not allowed for relays",
+            ParseError::InvalidIpv6Address => "malformed IPv6 address",
+            ParseError::InvalidHost => "host is not a valid domain name",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A `Url` that is known to be a valid, normalized `ws://` or `wss://` relay address
+///
+/// Unlike the general-purpose [`Url`], which may hold arbitrary (and
+/// possibly invalid) metadata links, a `RelayUrl` can only be constructed
+/// via [`RelayUrl::parse`], so holding one is a guarantee that it is usable
+/// as a relay address.
+///
+/// This Serializes/Deserializes from a string
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct RelayUrl(Url);
+
+impl RelayUrl {
+    /// Parse a string as a relay URL, normalizing it into canonical form
+    ///
+    /// Unlike [`Url::is_valid_relay_url`], which only returns a bool, this
+    /// reports which of the several ways a candidate relay address can be
+    /// invalid.
+    pub fn parse(s: &str) -> Result<RelayUrl, ParseError> {
+        let normalized = canonicalize(s);
+
+        // Check the raw (pre-IDNA) host for whitespace or malformed IPv6
+        // brackets before handing off to `http::Uri` below: `http::Uri`
+        // rejects both outright with an opaque `InvalidUri`, which would
+        // swallow these two failure modes before we could distinguish them.
+        if let Some((_, raw_authority, _)) = split_authority(&normalized) {
+            let (_, raw_host, _) = split_authority_parts(raw_authority);
+            if raw_host.starts_with('[') && !raw_host.ends_with(']') {
+                return Err(ParseError::InvalidIpv6Address);
+            }
+            if raw_host.chars().any(char::is_whitespace) {
+                return Err(ParseError::ContainsWhitespace);
+            }
+        }
+
+        let ascii = authority_to_ascii(&normalized);
+        let uri = ascii.parse::<http::Uri>().map_err(|_| ParseError::InvalidUri)?;
+
+        let scheme = uri.scheme_str().ok_or(ParseError::InvalidScheme)?;
+        if scheme != "ws" && scheme != "wss" {
+            return Err(ParseError::InvalidScheme);
+        }
+
+        let authority = uri.authority().ok_or(ParseError::EmptyHost)?;
+        let host = authority.host();
+        if host.is_empty() {
+            return Err(ParseError::EmptyHost);
+        }
+        if host.starts_with("localhost")
+            || host.starts_with("127.")
+            || host.starts_with("[::1]")
+            || host.starts_with("[0:")
+        {
+            return Err(ParseError::LoopbackNotAllowed);
+        }
+        if host_to_ascii(host).is_none() {
+            return Err(ParseError::InvalidHost);
+        }
+
+        Ok(RelayUrl(Url::from_canonical(normalized)))
+    }
+
+    /// Get reference to the inner, canonical string
+    pub fn inner(&self) -> &str {
+        self.0.inner()
+    }
+
+    // Mock data for testing
+    #[allow(dead_code)]
+    pub(crate) fn mock() -> RelayUrl {
+        RelayUrl(Url::mock())
     }
 }
 
+impl std::ops::Deref for RelayUrl {
+    type Target = Url;
+    fn deref(&self) -> &Url {
+        &self.0
+    }
+}
+
+impl fmt::Display for RelayUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for RelayUrl {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.0.inner())
+    }
+}
+
+impl<'de> Deserialize<'de> for RelayUrl {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(RelayUrlVisitor)
+    }
+}
+
+struct RelayUrlVisitor;
+
+impl Visitor<'_> for RelayUrlVisitor {
+    type Value = RelayUrl;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a string representing a relay URL")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<RelayUrl, E>
+    where
+        E: serde::de::Error,
+    {
+        RelayUrl::parse(v).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The default port for a URL scheme, if that scheme has one.
+fn default_port_for_scheme(scheme: &str) -> Option<u16> {
+    match scheme {
+        "ws" | "http" => Some(80),
+        "wss" | "https" => Some(443),
+        _ => None,
+    }
+}
+
+fn hex_val(c: char) -> Option<u8> {
+    c.to_digit(16).map(|d| d as u8)
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    matches!(byte, b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~')
+}
+
+/// Decode percent-escapes of RFC 3986 unreserved characters, and upper-case
+/// the hex digits of any escape that is left alone.
+fn normalize_percent_encoding(s: &str) -> String {
+    let chars: Vec<char> = s.chars().collect();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '%' && i + 2 < chars.len() {
+            if let (Some(hi), Some(lo)) = (hex_val(chars[i + 1]), hex_val(chars[i + 2])) {
+                let byte = hi * 16 + lo;
+                if is_unreserved(byte) {
+                    out.push(byte as char);
+                } else {
+                    out.push('%');
+                    out.push(chars[i + 1].to_ascii_uppercase());
+                    out.push(chars[i + 2].to_ascii_uppercase());
+                }
+                i += 3;
+                continue;
+            }
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Produce the canonical form of a URL string.
+///
+/// Falls back to returning the input unchanged if it doesn't parse as a URI
+/// at all, since normalization of something that isn't a URL is undefined.
+fn canonicalize(s: &str) -> String {
+    // `http::Uri` doesn't know about fragments, so split one off first, the
+    // same way `UrlParts::parse` does.
+    let (without_fragment, fragment) = match s.split_once('#') {
+        Some((rest, frag)) => (rest, Some(frag)),
+        None => (s, None),
+    };
+
+    // `http::Uri` also only understands ASCII, so punycode the host up front.
+    let ascii = authority_to_ascii(without_fragment);
+    let Ok(uri) = ascii.parse::<http::Uri>() else {
+        return s.to_owned();
+    };
+
+    let scheme = uri.scheme_str().map(str::to_ascii_lowercase);
+
+    let authority = uri.authority().map(|auth| {
+        let host = auth.host().to_ascii_lowercase();
+        let default_port = scheme.as_deref().and_then(default_port_for_scheme);
+        match auth.port_u16() {
+            Some(port) if Some(port) != default_port => format!("{host}:{port}"),
+            _ => host,
+        }
+    });
+
+    let path = normalize_percent_encoding(uri.path());
+    let query = uri.query().map(normalize_percent_encoding);
+    let fragment = fragment.map(normalize_percent_encoding);
+    let path = if path == "/" && query.is_none() && fragment.is_none() {
+        ""
+    } else if path == "/" {
+        "/"
+    } else {
+        path.as_str()
+    };
+
+    let mut out = String::new();
+    if let Some(scheme) = &scheme {
+        out.push_str(scheme);
+        out.push_str("://");
+    }
+    if let Some(authority) = &authority {
+        out.push_str(authority);
+    }
+    out.push_str(path);
+    if let Some(query) = &query {
+        out.push('?');
+        out.push_str(query);
+    }
+    if let Some(fragment) = &fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
 impl Serialize for Url {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -118,4 +664,208 @@ mod test {
     use super::*;
 
     test_serde! {Url, test_url_serde}
+    test_serde! {RelayUrl, test_relay_url_serde}
+
+    #[test]
+    fn test_normalize_lowercases_scheme_and_host() {
+        let u = Url::new("wss://Relay.Example.COM");
+        assert_eq!(u.normalize(), "wss://relay.example.com");
+    }
+
+    #[test]
+    fn test_normalize_strips_default_port() {
+        let u = Url::new("wss://relay.example.com:443");
+        assert_eq!(u.normalize(), "wss://relay.example.com");
+
+        let u = Url::new("ws://relay.example.com:80/path");
+        assert_eq!(u.normalize(), "ws://relay.example.com/path");
+
+        let u = Url::new("wss://relay.example.com:4848");
+        assert_eq!(u.normalize(), "wss://relay.example.com:4848");
+    }
+
+    #[test]
+    fn test_normalize_drops_bare_trailing_slash() {
+        let u = Url::new_normalized("wss://relay.example.com/");
+        assert_eq!(u.inner(), "wss://relay.example.com");
+
+        let u = Url::new_normalized("wss://relay.example.com/nostr/");
+        assert_eq!(u.inner(), "wss://relay.example.com/nostr/");
+    }
+
+    #[test]
+    fn test_normalize_is_idempotent() {
+        let once = Url::new_normalized("wss://Relay.Example.COM:443/a%2fb");
+        let twice = Url::new_normalized(once.inner());
+        assert_eq!(once.inner(), twice.inner());
+    }
+
+    #[test]
+    fn test_normalize_percent_encoding() {
+        let u = Url::new("wss://relay.example.com/%7euser/%2f");
+        assert_eq!(u.normalize(), "wss://relay.example.com/~user/%2F");
+    }
+
+    #[test]
+    fn test_normalize_preserves_fragment() {
+        let u = Url::new("wss://Relay.Example.COM/%7euser#Some%7eTag");
+        assert_eq!(u.normalize(), "wss://relay.example.com/~user#Some~Tag");
+    }
+
+    #[test]
+    fn test_normalize_keeps_bare_trailing_slash_with_fragment() {
+        let u = Url::new_normalized("wss://relay.example.com/#tag");
+        assert_eq!(u.inner(), "wss://relay.example.com/#tag");
+    }
+
+    #[test]
+    fn test_new_preserves_trailing_slash_in_fragment() {
+        let u = Url::new("wss://relay.example.com/page#section/");
+        assert_eq!(u.inner(), "wss://relay.example.com/page#section/");
+    }
+
+    #[test]
+    fn test_normalize_idna_host() {
+        let u = Url::new("wss://релей.рф");
+        assert_eq!(u.normalize(), "wss://xn--e1aamgx.xn--p1ai");
+    }
+
+    #[test]
+    fn test_idna_unicode_and_punycode_collapse() {
+        let unicode = Url::new_normalized("wss://релей.рф");
+        let punycode = Url::new_normalized("wss://xn--e1aamgx.xn--p1ai");
+        assert_eq!(unicode.inner(), punycode.inner());
+    }
+
+    #[test]
+    fn test_unicode_host_round_trips() {
+        let u = Url::new_normalized("wss://релей.рф");
+        assert_eq!(u.unicode_host().as_deref(), Some("релей.рф"));
+    }
+
+    #[test]
+    fn test_is_valid_relay_url_rejects_bad_idna() {
+        let u = Url::new("wss://exa--mple.xn--zz");
+        assert!(!u.is_valid_relay_url());
+    }
+
+    #[test]
+    fn test_is_valid_relay_url_rejects_ipv6_loopback() {
+        let u = Url::new("wss://[::1]");
+        assert!(!u.is_valid_relay_url());
+    }
+
+    #[test]
+    fn test_component_accessors() {
+        let u = Url::new("wss://relay.example.com:4848/nostr?foo=bar#tag");
+        assert_eq!(u.scheme(), Some("wss"));
+        assert_eq!(u.host(), Some("relay.example.com"));
+        assert_eq!(u.port(), Some(4848));
+        assert_eq!(u.path(), "/nostr");
+        assert_eq!(u.query(), Some("foo=bar"));
+        assert_eq!(u.fragment(), Some("tag"));
+    }
+
+    #[test]
+    fn test_component_accessors_repeated_calls_use_cache() {
+        let u = Url::new("wss://relay.example.com/nostr");
+        // Both calls borrow from the same cached parse, so they point at the same bytes.
+        assert!(std::ptr::eq(u.host().unwrap(), u.host().unwrap()));
+        assert!(std::ptr::eq(u.path(), u.path()));
+    }
+
+    #[test]
+    fn test_equality_ignores_component_cache() {
+        let a = Url::new("wss://relay.example.com");
+        let b = a.clone();
+        // Force `a`'s cache to populate, `b`'s stays empty.
+        let _ = a.host();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_relay_url_parse_normalizes() {
+        let r = RelayUrl::parse("WSS://Relay.Example.COM:443/").unwrap();
+        assert_eq!(r.inner(), "wss://relay.example.com");
+    }
+
+    #[test]
+    fn test_relay_url_parse_rejects_bad_scheme() {
+        assert_eq!(
+            RelayUrl::parse("https://relay.example.com"),
+            Err(ParseError::InvalidScheme)
+        );
+    }
+
+    #[test]
+    fn test_relay_url_parse_rejects_loopback() {
+        assert_eq!(
+            RelayUrl::parse("wss://127.0.0.1"),
+            Err(ParseError::LoopbackNotAllowed)
+        );
+        assert_eq!(
+            RelayUrl::parse("wss://localhost"),
+            Err(ParseError::LoopbackNotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_relay_url_parse_rejects_whitespace_in_host() {
+        assert_eq!(
+            RelayUrl::parse("wss://exa mple.com"),
+            Err(ParseError::ContainsWhitespace)
+        );
+    }
+
+    #[test]
+    fn test_relay_url_parse_rejects_malformed_ipv6() {
+        assert_eq!(
+            RelayUrl::parse("wss://[::1"),
+            Err(ParseError::InvalidIpv6Address)
+        );
+    }
+
+    #[test]
+    fn test_relay_url_parse_rejects_invalid_idna() {
+        assert_eq!(
+            RelayUrl::parse("wss://exa--mple.xn--zz"),
+            Err(ParseError::InvalidHost)
+        );
+    }
+
+    #[test]
+    fn test_relay_url_derefs_to_url_accessors() {
+        let r = RelayUrl::parse("wss://relay.example.com:4848/nostr").unwrap();
+        assert_eq!(r.host(), Some("relay.example.com"));
+        assert_eq!(r.port(), Some(4848));
+    }
+
+    #[test]
+    fn test_scheme_kind() {
+        assert_eq!(
+            Url::new("wss://relay.example.com").scheme_kind(),
+            Some(Scheme::Wss)
+        );
+        assert_eq!(
+            Url::new("nostr:npub1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq")
+                .scheme_kind(),
+            Some(Scheme::Nostr)
+        );
+        assert_eq!(
+            Url::new("magnet:?xt=foo").scheme_kind(),
+            Some(Scheme::Other("magnet".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_nostr_bech32_payload() {
+        let u = Url::new("nostr:npub1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq");
+        assert_eq!(
+            u.nostr_bech32_payload(),
+            Some("npub1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq")
+        );
+
+        let u = Url::new("wss://relay.example.com");
+        assert_eq!(u.nostr_bech32_payload(), None);
+    }
 }